@@ -8,6 +8,7 @@ fn main() {
     let bindings = bindgen::Builder::default()
         .header("wrapper.h")
         .allowlist_function("EMD_wrap")
+        .allowlist_function("EMD_wrap_return_sparse")
         .clang_arg("-xc++") // https://github.com/rust-lang/rust-bindgen/issues/1855
         .clang_arg("-std=c++14")
         .generate()
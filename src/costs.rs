@@ -0,0 +1,195 @@
+use ndarray::{Array1, Array2, ArrayView1, Axis};
+
+/// A distance metric used by [pairwise_costs] to turn two point clouds into a cost matrix.
+pub enum Metric {
+    /// The squared Euclidean distance, `sum((x - y)^2)`.
+    SqEuclidean,
+    /// The Euclidean distance, `sqrt(sum((x - y)^2))`.
+    Euclidean,
+    /// The Manhattan (L1, "city block") distance, `sum(|x - y|)`.
+    Cityblock,
+    /// A user-supplied distance function between two samples.
+    Custom(fn(&ArrayView1<f64>, &ArrayView1<f64>) -> f64),
+}
+
+impl Metric {
+    fn distance(&self, x: &ArrayView1<f64>, y: &ArrayView1<f64>) -> f64 {
+        match self {
+            Metric::SqEuclidean => x
+                .iter()
+                .zip(y.iter())
+                .map(|(&a, &b)| (a - b) * (a - b))
+                .sum(),
+            Metric::Euclidean => Metric::SqEuclidean.distance(x, y).sqrt(),
+            Metric::Cityblock => x.iter().zip(y.iter()).map(|(&a, &b)| (a - b).abs()).sum(),
+            Metric::Custom(f) => f(x, y),
+        }
+    }
+}
+
+/// Build the pairwise cost matrix between two point clouds, using the given [Metric].
+///
+/// * `xs` - The source point cloud; one sample per row.
+/// * `xt` - The target point cloud; one sample per row. Must have the same number of columns
+/// (features) as `xs`.
+///
+/// Returns a matrix of shape `|xs|`x`|xt|`, suitable for use as the `costs` argument of [crate::emd]
+/// or [crate::EmdSolver].
+///
+/// # Examples
+///
+/// ```
+/// use just_emd::{pairwise_costs, Metric};
+/// use ndarray::array;
+///
+/// let xs = array![[0.0, 0.0], [1.0, 0.0]];
+/// let xt = array![[0.0, 1.0]];
+///
+/// let costs = pairwise_costs(&xs, &xt, Metric::Euclidean);
+/// assert_eq!(costs, array![[1.0], [2.0_f64.sqrt()]]);
+/// ```
+pub fn pairwise_costs(xs: &Array2<f64>, xt: &Array2<f64>, metric: Metric) -> Array2<f64> {
+    let mut costs = Array2::<f64>::zeros((xs.len_of(Axis(0)), xt.len_of(Axis(0))));
+
+    for (i, x) in xs.axis_iter(Axis(0)).enumerate() {
+        for (j, y) in xt.axis_iter(Axis(0)).enumerate() {
+            costs[(i, j)] = metric.distance(&x, &y);
+        }
+    }
+
+    costs
+}
+
+/// A normalization to apply to a cost matrix before solving an EMD instance, via
+/// [crate::EmdSolver::normalize]. This matters because the network simplex's `EPSILON`-scaled
+/// tolerances and iteration behavior are sensitive to the absolute magnitude of the costs, so
+/// normalizing improves numerical robustness for widely-scaled inputs.
+///
+/// Both variants panic if the cost matrix contains a `NaN` entry, since there is no sane
+/// normalization factor to compute in that case. An empty cost matrix is a no-op for both
+/// variants (there is nothing to rescale).
+pub enum Norm {
+    /// Divide the cost matrix by its maximum entry.
+    Max,
+    /// Divide the cost matrix by its median entry.
+    Median,
+}
+
+impl Norm {
+    pub(crate) fn apply(&self, costs: &mut Array2<f64>) {
+        let factor = match self {
+            Norm::Max => max(costs),
+            Norm::Median => median(costs),
+        };
+
+        if factor != 0.0 {
+            *costs /= factor;
+        }
+    }
+}
+
+fn max(costs: &Array2<f64>) -> f64 {
+    costs
+        .iter()
+        .cloned()
+        .fold(f64::MIN, |acc, x| match acc.partial_cmp(&x) {
+            Some(std::cmp::Ordering::Less) => x,
+            Some(_) => acc,
+            None => panic!("costs must not be NaN"),
+        })
+}
+
+/// Returns `1.0` (a no-op factor) for an empty cost matrix, since there is no median to compute
+/// and nothing to rescale either way.
+fn median(costs: &Array2<f64>) -> f64 {
+    let mut values: Array1<f64> = costs.iter().cloned().collect();
+    if values.is_empty() {
+        return 1.0;
+    }
+
+    let mid = values.len() / 2;
+    let (_, &mut median, _) = values
+        .as_slice_mut()
+        .expect("costs array is contiguous")
+        .select_nth_unstable_by(mid, |a, b| a.partial_cmp(b).expect("costs must not be NaN"));
+    median
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::array;
+
+    #[test]
+    fn test_pairwise_costs_sq_euclidean() {
+        let xs = array![[0.0, 0.0], [1.0, 0.0]];
+        let xt = array![[0.0, 1.0]];
+
+        let costs = pairwise_costs(&xs, &xt, Metric::SqEuclidean);
+        assert_eq!(costs, array![[1.0], [2.0]]);
+    }
+
+    #[test]
+    fn test_pairwise_costs_cityblock() {
+        let xs = array![[0.0, 0.0], [1.0, 0.0]];
+        let xt = array![[0.0, 1.0]];
+
+        let costs = pairwise_costs(&xs, &xt, Metric::Cityblock);
+        assert_eq!(costs, array![[1.0], [2.0]]);
+    }
+
+    #[test]
+    fn test_pairwise_costs_custom() {
+        let xs = array![[0.0, 0.0]];
+        let xt = array![[3.0, 4.0]];
+
+        let costs = pairwise_costs(
+            &xs,
+            &xt,
+            Metric::Custom(|x, y| (x[0] - y[0]).abs().max((x[1] - y[1]).abs())),
+        );
+        assert_eq!(costs, array![[4.0]]);
+    }
+
+    #[test]
+    fn test_norm_max() {
+        let mut costs = array![[0.0, 1.0], [2.0, 4.0]];
+        Norm::Max.apply(&mut costs);
+        assert_eq!(costs, array![[0.0, 0.25], [0.5, 1.0]]);
+    }
+
+    #[test]
+    fn test_norm_median() {
+        let mut costs = array![[1.0, 2.0], [3.0, 4.0]];
+        Norm::Median.apply(&mut costs);
+        assert_eq!(costs, array![[1.0 / 3.0, 2.0 / 3.0], [1.0, 4.0 / 3.0]]);
+    }
+
+    #[test]
+    fn test_norm_median_empty_costs_is_a_no_op() {
+        let mut costs = Array2::<f64>::zeros((0, 0));
+        Norm::Median.apply(&mut costs);
+        assert_eq!(costs, Array2::<f64>::zeros((0, 0)));
+    }
+
+    #[test]
+    fn test_norm_max_empty_costs_is_a_no_op() {
+        let mut costs = Array2::<f64>::zeros((0, 0));
+        Norm::Max.apply(&mut costs);
+        assert_eq!(costs, Array2::<f64>::zeros((0, 0)));
+    }
+
+    #[test]
+    #[should_panic(expected = "costs must not be NaN")]
+    fn test_norm_max_panics_on_nan() {
+        let mut costs = array![[0.0, f64::NAN], [2.0, 4.0]];
+        Norm::Max.apply(&mut costs);
+    }
+
+    #[test]
+    #[should_panic(expected = "costs must not be NaN")]
+    fn test_norm_median_panics_on_nan() {
+        let mut costs = array![[0.0, f64::NAN], [2.0, 4.0]];
+        Norm::Median.apply(&mut costs);
+    }
+}
@@ -2,10 +2,17 @@
 
 use ndarray::{Array1, Array2, Axis};
 use thiserror::Error;
-use wrap::c_emd_wrapper;
+use wrap::{c_emd_wrapper, c_emd_wrapper_sparse};
 
+mod costs;
+mod sinkhorn;
+mod wasserstein_1d;
 mod wrap;
 
+pub use costs::{pairwise_costs, Metric, Norm};
+pub use sinkhorn::SinkhornSolver;
+pub use wasserstein_1d::wasserstein_1d;
+
 #[derive(Error, Debug, PartialEq)]
 /// An error that is encountered in the computation of the EMD.
 pub enum EmdError {
@@ -19,6 +26,36 @@ pub enum EmdError {
     /// An invalid (negative or zero) number of iterations was supplied.
     #[error("Number of iterations ({0}) must be > 0")]
     InvalidIterations(i32),
+    /// An invalid (negative or zero) regularization strength was supplied to [SinkhornSolver].
+    #[error("Regularization strength ({0}) must be > 0")]
+    InvalidRegularization(f64),
+    /// A mismatch is detected between the number of positions and the number of weights of a
+    /// 1-D distribution passed to [wasserstein_1d].
+    #[error("{0:?} distribution has {1} positions but {2} weights")]
+    PositionWeightDimensionError(WhichVector, usize, usize),
+    /// A negative or non-finite (`NaN`/infinite) weight was found in the source or target
+    /// population. Named for the common case, but also covers `NaN`/infinite entries, since
+    /// those are just as unusable as a negative one. Detected before any rescaling is applied,
+    /// so `index`/`value` point at the offending entry as supplied by the caller.
+    #[error("{vector:?} weight at index {index} is invalid (must be a non-negative, finite number): {value}")]
+    NegativeWeight {
+        /// Which of the two weight vectors the invalid entry was found in.
+        vector: WhichVector,
+        /// The index of the invalid entry.
+        index: usize,
+        /// The invalid value.
+        value: f64,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Identifies which of the two weight vectors (source or target population) an [EmdError]
+/// relates to.
+pub enum WhichVector {
+    /// The source population.
+    Source,
+    /// The target population.
+    Target,
 }
 
 #[derive(Error, Debug, PartialEq)]
@@ -57,6 +94,47 @@ pub struct EmdResult {
     pub emd: f64,
 }
 
+#[derive(Debug, Clone, PartialEq)]
+/// The result of an EMD computation in coordinate (sparse) form.
+///
+/// A network-simplex optimum is a basic feasible solution, which has at most
+/// `|source_weights| + |target_weights| - 1` non-zero arcs. For large problems this avoids
+/// materializing an almost entirely zero `Array2<f64>` flow matrix. `rows[k]`, `cols[k]` and
+/// `values[k]` together describe one non-zero entry of the flow matrix: `values[k]` units flow
+/// from source item `rows[k]` to target item `cols[k]`.
+pub struct SparseEmdResult {
+    /// The row (source item) index of each non-zero flow entry.
+    pub rows: Array1<usize>,
+    /// The column (target item) index of each non-zero flow entry.
+    pub cols: Array1<usize>,
+    /// The amount of flow for each non-zero entry.
+    pub values: Array1<f64>,
+    /// The Earth Mover's Distance computed between the two populations
+    pub emd: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// The result of an EMD computation, including the dual potentials of the network simplex
+/// solution.
+pub struct EmdResultWithDuals {
+    /// The optimal transport solution matrix
+    pub flow_matrix: Array2<f64>,
+    /// The Earth Mover's Distance computed between the two populations
+    pub emd: f64,
+    /// The dual potential associated with each source item, i.e. the Lagrange multiplier of
+    /// the source mass constraint. The gradient of the EMD w.r.t. `source_weights[i]` is
+    /// `dual_source[i]`.
+    ///
+    /// The potentials are only defined up to an additive constant shared between
+    /// `dual_source` and `dual_target`; this crate uses whatever normalization the network
+    /// simplex solver settles on (the potential of its root node is zero), so only differences
+    /// of potentials, or comparisons between solves of the same instance, are meaningful.
+    pub dual_source: Array1<f64>,
+    /// The dual potential associated with each target item, i.e. the Lagrange multiplier of
+    /// the target mass constraint. See [Self::dual_source] for the normalization convention.
+    pub dual_target: Array1<f64>,
+}
+
 /// A struct used to solve a particular EMD instance.
 ///
 /// # Examples
@@ -94,6 +172,9 @@ pub struct EmdSolver<'a> {
     /// The maximum number of iterations to perform in the network simplex algorithm. By default,
     /// this is 100000 iterations.
     iterations: i32,
+    /// An optional normalization to apply to `costs` before solving. By default, no
+    /// normalization is applied.
+    normalization: Option<Norm>,
 }
 
 impl<'a> EmdSolver<'a> {
@@ -113,6 +194,7 @@ impl<'a> EmdSolver<'a> {
             target,
             costs,
             iterations: 100000,
+            normalization: None,
         }
     }
 
@@ -123,10 +205,43 @@ impl<'a> EmdSolver<'a> {
         self
     }
 
+    /// Normalize `costs` by the given [Norm] before solving. This matters because the network
+    /// simplex's `EPSILON`-scaled tolerances and iteration behavior are sensitive to the
+    /// absolute magnitude of the costs, so normalization improves numerical robustness for
+    /// widely-scaled inputs. By default, no normalization is applied.
+    pub fn normalize(mut self, norm: Norm) -> Self {
+        self.normalization = Some(norm);
+        self
+    }
+
+    fn apply_normalization(&mut self) {
+        if let Some(norm) = &self.normalization {
+            norm.apply(self.costs);
+        }
+    }
+
     /// Solve the EMD instance.
     pub fn solve(&mut self) -> Result<EmdResult, EmdError> {
+        self.apply_normalization();
         emd(self.source, self.target, self.costs, self.iterations)
     }
+
+    /// Solve the EMD instance, returning the flow matrix in sparse (coordinate) form.
+    ///
+    /// See [emd_sparse] for details.
+    pub fn solve_sparse(&mut self) -> Result<SparseEmdResult, EmdError> {
+        self.apply_normalization();
+        emd_sparse(self.source, self.target, self.costs, self.iterations)
+    }
+
+    /// Solve the EMD instance, additionally returning the dual potentials of the network
+    /// simplex solution.
+    ///
+    /// See [emd_with_duals] for details.
+    pub fn solve_with_duals(&mut self) -> Result<EmdResultWithDuals, EmdError> {
+        self.apply_normalization();
+        emd_with_duals(self.source, self.target, self.costs, self.iterations)
+    }
 }
 
 /// Compute the Earth Mover's Distance between two populations
@@ -166,6 +281,7 @@ pub fn emd(
         return Err(EmdError::InvalidIterations(iterations));
     }
     check_emd_input_shapes(source_weights, target_weights, costs)?;
+    validate_weights(source_weights, target_weights)?;
 
     // From python optimal transport
     *target_weights *= source_weights.sum() / target_weights.sum();
@@ -182,13 +298,122 @@ pub fn emd(
     }
 }
 
+/// Compute the Earth Mover's Distance between two populations, returning the flow matrix in
+/// sparse (coordinate) form.
+///
+/// A network-simplex optimum is a basic feasible solution with at most
+/// |`source_weights`| + |`target_weights`| - 1 non-zero arcs, so for large problems this avoids
+/// the O(n*m) allocation of the dense flow matrix returned by [emd].
+///
+/// Arguments are the same as for [emd].
+///
+/// # Examples
+///
+/// ```
+/// use just_emd::emd_sparse;
+/// use ndarray::array;
+///
+/// let mut source = array![0.3, 0.4, 0.2];
+/// let mut target = array![0.2, 0.8, 0.0];
+///
+/// // Absolute difference as cost function
+/// let mut costs = array![
+///     [0.0, 1.0, 2.0],
+///     [1.0, 0.0, 1.0],
+///     [2.0, 1.0, 0.0]
+/// ];
+///
+/// let res = emd_sparse(&mut source, &mut target, &mut costs, 10000).unwrap();
+/// assert!(0.32 - res.emd <= 1e-5);
+/// ```
+pub fn emd_sparse(
+    source_weights: &mut Array1<f64>,
+    target_weights: &mut Array1<f64>,
+    costs: &mut Array2<f64>,
+    iterations: i32,
+) -> Result<SparseEmdResult, EmdError> {
+    if iterations <= 0 {
+        return Err(EmdError::InvalidIterations(iterations));
+    }
+    check_emd_input_shapes(source_weights, target_weights, costs)?;
+    validate_weights(source_weights, target_weights)?;
+
+    // From python optimal transport
+    *target_weights *= source_weights.sum() / target_weights.sum();
+
+    let (rows, cols, values, cost, _a, _b, code) =
+        c_emd_wrapper_sparse(source_weights, target_weights, costs, iterations);
+    if code == 1 {
+        Ok(SparseEmdResult {
+            rows,
+            cols,
+            values,
+            emd: cost,
+        })
+    } else {
+        Err(FastTransportError::from(code))?
+    }
+}
+
+/// Compute the Earth Mover's Distance between two populations, additionally returning the dual
+/// potentials (`alpha`/`beta` in the network simplex formulation) of the solution.
+///
+/// The potentials are the Lagrange multipliers of the source/target mass constraints; they are
+/// needed to differentiate the EMD w.r.t. the input weights, or to build Wasserstein
+/// barycenters. See [EmdResultWithDuals::dual_source] for the normalization convention.
+///
+/// Arguments are the same as for [emd].
+///
+/// # Examples
+///
+/// ```
+/// use just_emd::emd_with_duals;
+/// use ndarray::array;
+///
+/// let mut source = array![0.5, 0.5];
+/// let mut target = array![0.5, 0.5];
+///
+/// let mut costs = array![[0.0, 1.0], [1.0, 0.0]];
+///
+/// let res = emd_with_duals(&mut source, &mut target, &mut costs, 10000).unwrap();
+/// assert_eq!(res.emd, 0.0);
+/// ```
+pub fn emd_with_duals(
+    source_weights: &mut Array1<f64>,
+    target_weights: &mut Array1<f64>,
+    costs: &mut Array2<f64>,
+    iterations: i32,
+) -> Result<EmdResultWithDuals, EmdError> {
+    if iterations <= 0 {
+        return Err(EmdError::InvalidIterations(iterations));
+    }
+    check_emd_input_shapes(source_weights, target_weights, costs)?;
+    validate_weights(source_weights, target_weights)?;
+
+    // From python optimal transport
+    *target_weights *= source_weights.sum() / target_weights.sum();
+
+    let (flow_matrix, cost, dual_source, dual_target, code) =
+        c_emd_wrapper(source_weights, target_weights, costs, iterations);
+    if code == 1 {
+        Ok(EmdResultWithDuals {
+            flow_matrix,
+            emd: cost,
+            dual_source,
+            dual_target,
+        })
+    } else {
+        Err(FastTransportError::from(code))?
+    }
+}
+
 /// Check that the dimensions of both populations match the dimensions of the cost matrix.
 ///
 /// The length of `a` should match the number of rows in the costs matrix, and the
 /// length of `b` should match the number of rows.
 ///
 /// If this does not hold, an [EmdError] is returned.
-fn check_emd_input_shapes(
+pub(crate) fn check_emd_input_shapes(
     a: &Array1<f64>,
     b: &Array1<f64>,
     costs: &Array2<f64>,
@@ -211,6 +436,38 @@ fn check_emd_input_shapes(
     }
 }
 
+/// Check that neither `source_weights` nor `target_weights` contain a negative or non-finite
+/// entry.
+///
+/// This must run before `target_weights` is rescaled by `source_weights.sum() /
+/// target_weights.sum()`, since the upstream fast_transport wrapper treats a negative entry as
+/// an infeasibility signal and would otherwise only surface it (if at all) as an opaque
+/// [FastTransportError::Infeasible], after the rescaling has already corrupted `target_weights`.
+///
+/// If this does not hold, an [EmdError::NegativeWeight] is returned, pinpointing the offending
+/// entry.
+pub(crate) fn validate_weights(
+    source_weights: &Array1<f64>,
+    target_weights: &Array1<f64>,
+) -> Result<(), EmdError> {
+    validate_weight_vector(WhichVector::Source, source_weights)?;
+    validate_weight_vector(WhichVector::Target, target_weights)?;
+    Ok(())
+}
+
+fn validate_weight_vector(vector: WhichVector, weights: &Array1<f64>) -> Result<(), EmdError> {
+    for (index, &value) in weights.iter().enumerate() {
+        if !value.is_finite() || value < 0.0 {
+            return Err(EmdError::NegativeWeight {
+                vector,
+                index,
+                value,
+            });
+        }
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -265,6 +522,54 @@ mod tests {
             )));
     }
 
+    #[test]
+    /// From the examples in the python-optimal-transport docs
+    fn test_ot_simple_example_sparse() {
+        let mut a = array![0.5, 0.5];
+        let mut b = array![0.5, 0.5];
+
+        let mut costs = array![[0.0, 1.0], [1.0, 0.0]];
+
+        let result = emd_sparse(&mut a, &mut b, &mut costs, 10000).unwrap();
+
+        assert_eq!(result.emd, 0.0);
+        assert_eq!(result.rows, array![0, 1]);
+        assert_eq!(result.cols, array![0, 1]);
+        assert_eq!(result.values, array![0.5, 0.5]);
+    }
+
+    #[test]
+    /// From the examples in the python-optimal-transport docs
+    fn test_ot_simple_example_with_duals() {
+        let mut a = array![0.5, 0.5];
+        let mut b = array![0.5, 0.5];
+
+        let mut costs = array![[0.0, 1.0], [1.0, 0.0]];
+
+        let result = emd_with_duals(&mut a, &mut b, &mut costs, 10000).unwrap();
+
+        assert_eq!(result.emd, 0.0);
+        assert_eq!(result.flow_matrix, array![[0.5, 0.0], [0.0, 0.5]]);
+
+        // Complementary slackness: the duals are feasible (`dual_source[i] + dual_target[j] <=
+        // costs[i,j]` everywhere) with equality exactly on the arcs carrying flow.
+        for i in 0..2 {
+            for j in 0..2 {
+                let slack = costs[(i, j)] - (result.dual_source[i] + result.dual_target[j]);
+                assert!(
+                    slack >= -1e-9,
+                    "dual infeasible at ({i},{j}): slack {slack}"
+                );
+                if result.flow_matrix[(i, j)] > 0.0 {
+                    assert!(
+                        slack.abs() < 1e-9,
+                        "complementary slackness violated at ({i},{j}): slack {slack}"
+                    );
+                }
+            }
+        }
+    }
+
     #[test]
     fn test_max_iter() {
         // Random example that needs more than one iter; Found by trial and error
@@ -278,4 +583,70 @@ mod tests {
             EmdError::FastTransportError(FastTransportError::MaxIterReached)
         )));
     }
+
+    #[test]
+    fn test_negative_source_weight_rejected() {
+        let mut a = array![0.5, -0.1, 0.6];
+        let mut b = array![0.5, 0.5, 0.0];
+        let mut costs = Array2::from_elem((3, 3), 1.0);
+
+        let res = emd(&mut a, &mut b, &mut costs, 10000);
+
+        assert!(res.is_err_and(|err| err
+            == EmdError::NegativeWeight {
+                vector: WhichVector::Source,
+                index: 1,
+                value: -0.1,
+            }));
+    }
+
+    #[test]
+    fn test_negative_target_weight_rejected() {
+        let mut a = array![0.5, 0.5];
+        let mut b = array![1.0, -1.0];
+        let mut costs = Array2::from_elem((2, 2), 1.0);
+
+        let res = emd(&mut a, &mut b, &mut costs, 10000);
+
+        assert!(res.is_err_and(|err| err
+            == EmdError::NegativeWeight {
+                vector: WhichVector::Target,
+                index: 1,
+                value: -1.0,
+            }));
+    }
+
+    #[test]
+    fn test_nan_weight_rejected() {
+        let mut a = array![0.5, f64::NAN];
+        let mut b = array![0.5, 0.5];
+        let mut costs = Array2::from_elem((2, 2), 1.0);
+
+        let res = emd(&mut a, &mut b, &mut costs, 10000);
+
+        assert!(res.is_err_and(|err| matches!(
+            err,
+            EmdError::NegativeWeight {
+                vector: WhichVector::Source,
+                index: 1,
+                value,
+            } if value.is_nan()
+        )));
+    }
+
+    #[test]
+    fn test_infinite_weight_rejected() {
+        let mut a = array![0.5, 0.5];
+        let mut b = array![f64::INFINITY, 0.5];
+        let mut costs = Array2::from_elem((2, 2), 1.0);
+
+        let res = emd(&mut a, &mut b, &mut costs, 10000);
+
+        assert!(res.is_err_and(|err| err
+            == EmdError::NegativeWeight {
+                vector: WhichVector::Target,
+                index: 0,
+                value: f64::INFINITY,
+            }));
+    }
 }
@@ -0,0 +1,231 @@
+use ndarray::Array1;
+
+use crate::{validate_weights, EmdError, WhichVector};
+
+/// Compute the 1-D Wasserstein-1 distance between two weighted point sets on the real line.
+///
+/// This is a closed-form alternative to [crate::emd] for the common case of comparing 1-D
+/// histograms or samples: rather than invoking the full network simplex over an `n`x`m` cost
+/// matrix, both supports are sorted by position and their cumulative distribution functions are
+/// walked in a single merge pass, accumulating `|F(x) - G(x)| * dx` between consecutive
+/// breakpoints. This runs in `O(n log n + m log m)` with no cost matrix and no call into the
+/// `fast_transport` C++ library.
+///
+/// * `source_positions` / `source_weights` - The positions and relative frequencies of the
+/// items in the source population. Must have the same length.
+/// * `target_positions` / `target_weights` - The positions and relative frequencies of the
+/// items in the target population. Must have the same length.
+///
+/// As in [crate::emd], `target_weights` is rescaled so that its total mass matches
+/// `source_weights`'s, and a negative or non-finite weight in either vector is rejected with
+/// [EmdError::NegativeWeight] before that rescaling happens.
+///
+/// # Examples
+///
+/// ```
+/// use just_emd::wasserstein_1d;
+/// use ndarray::array;
+///
+/// let source_positions = array![0.0, 1.0];
+/// let source_weights = array![0.5, 0.5];
+///
+/// let target_positions = array![0.0, 2.0];
+/// let target_weights = array![0.5, 0.5];
+///
+/// let dist = wasserstein_1d(&source_positions, &source_weights, &target_positions, &target_weights).unwrap();
+/// assert_eq!(dist, 0.5);
+/// ```
+pub fn wasserstein_1d(
+    source_positions: &Array1<f64>,
+    source_weights: &Array1<f64>,
+    target_positions: &Array1<f64>,
+    target_weights: &Array1<f64>,
+) -> Result<f64, EmdError> {
+    if source_positions.len() != source_weights.len() {
+        return Err(EmdError::PositionWeightDimensionError(
+            WhichVector::Source,
+            source_positions.len(),
+            source_weights.len(),
+        ));
+    }
+    if target_positions.len() != target_weights.len() {
+        return Err(EmdError::PositionWeightDimensionError(
+            WhichVector::Target,
+            target_positions.len(),
+            target_weights.len(),
+        ));
+    }
+    validate_weights(source_weights, target_weights)?;
+
+    let total = source_weights.sum();
+    let target_total = target_weights.sum();
+
+    let mut source: Vec<(f64, f64)> = source_positions
+        .iter()
+        .zip(source_weights.iter())
+        .map(|(&p, &w)| (p, w))
+        .collect();
+    source.sort_by(|a, b| a.0.partial_cmp(&b.0).expect("positions must not be NaN"));
+
+    let mut target: Vec<(f64, f64)> = target_positions
+        .iter()
+        .zip(target_weights.iter())
+        .map(|(&p, &w)| (p, w * total / target_total))
+        .collect();
+    target.sort_by(|a, b| a.0.partial_cmp(&b.0).expect("positions must not be NaN"));
+
+    let mut i = 0;
+    let mut j = 0;
+    let mut cum_source = 0.0;
+    let mut cum_target = 0.0;
+    let mut prev_x = f64::NEG_INFINITY;
+    let mut distance = 0.0;
+
+    while i < source.len() || j < target.len() {
+        let next_x = match (source.get(i), target.get(j)) {
+            (Some(&(sx, _)), Some(&(tx, _))) => sx.min(tx),
+            (Some(&(sx, _)), None) => sx,
+            (None, Some(&(tx, _))) => tx,
+            (None, None) => unreachable!(),
+        };
+
+        if prev_x.is_finite() {
+            let source_cdf = cum_source / total;
+            let target_cdf = cum_target / total;
+            distance += (source_cdf - target_cdf).abs() * (next_x - prev_x);
+        }
+
+        while let Some(&(sx, sw)) = source.get(i) {
+            if sx != next_x {
+                break;
+            }
+            cum_source += sw;
+            i += 1;
+        }
+        while let Some(&(tx, tw)) = target.get(j) {
+            if tx != next_x {
+                break;
+            }
+            cum_target += tw;
+            j += 1;
+        }
+
+        prev_x = next_x;
+    }
+
+    Ok(distance)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::emd;
+    use ndarray::array;
+    use rand::Rng;
+
+    #[test]
+    fn test_wasserstein_1d_simple_example() {
+        let source_positions = array![0.0, 1.0];
+        let source_weights = array![0.5, 0.5];
+
+        let target_positions = array![0.0, 2.0];
+        let target_weights = array![0.5, 0.5];
+
+        let dist = wasserstein_1d(
+            &source_positions,
+            &source_weights,
+            &target_positions,
+            &target_weights,
+        )
+        .unwrap();
+
+        assert_eq!(dist, 0.5);
+    }
+
+    #[test]
+    fn test_wasserstein_1d_dimension_mismatch() {
+        let source_positions = array![0.0, 1.0, 2.0];
+        let source_weights = array![0.5, 0.5];
+
+        let target_positions = array![0.0];
+        let target_weights = array![1.0];
+
+        let res = wasserstein_1d(
+            &source_positions,
+            &source_weights,
+            &target_positions,
+            &target_weights,
+        );
+
+        assert!(res.is_err_and(
+            |err| err == EmdError::PositionWeightDimensionError(WhichVector::Source, 3, 2)
+        ));
+    }
+
+    #[test]
+    fn test_wasserstein_1d_negative_weight_rejected() {
+        let source_positions = array![0.0, 1.0];
+        let source_weights = array![0.5, -0.5];
+
+        let target_positions = array![0.0, 1.0];
+        let target_weights = array![0.5, 0.5];
+
+        let res = wasserstein_1d(
+            &source_positions,
+            &source_weights,
+            &target_positions,
+            &target_weights,
+        );
+
+        assert!(res.is_err_and(|err| err
+            == EmdError::NegativeWeight {
+                vector: WhichVector::Source,
+                index: 1,
+                value: -0.5,
+            }));
+    }
+
+    #[test]
+    fn test_wasserstein_1d_matches_exact_emd_on_random_instances() {
+        let mut rng = rand::thread_rng();
+
+        for _ in 0..20 {
+            let n = rng.gen_range(2..8);
+            let m = rng.gen_range(2..8);
+
+            let source_positions: Array1<f64> =
+                Array1::from_iter((0..n).map(|_| rng.gen_range(-10.0..10.0)));
+            let mut source_weights: Array1<f64> =
+                Array1::from_iter((0..n).map(|_| rng.gen_range(0.1..1.0)));
+            source_weights /= source_weights.sum();
+
+            let target_positions: Array1<f64> =
+                Array1::from_iter((0..m).map(|_| rng.gen_range(-10.0..10.0)));
+            let mut target_weights: Array1<f64> =
+                Array1::from_iter((0..m).map(|_| rng.gen_range(0.1..1.0)));
+            target_weights /= target_weights.sum();
+
+            let expected = wasserstein_1d(
+                &source_positions,
+                &source_weights,
+                &target_positions,
+                &target_weights,
+            )
+            .unwrap();
+
+            let mut costs = Array1::from_iter(
+                source_positions
+                    .iter()
+                    .flat_map(|&sx| target_positions.iter().map(move |&tx| (sx - tx).abs())),
+            )
+            .into_shape((n, m))
+            .unwrap();
+
+            let mut a = source_weights.clone();
+            let mut b = target_weights.clone();
+            let actual = emd(&mut a, &mut b, &mut costs, 100000).unwrap().emd;
+
+            assert!((expected - actual).abs() < 1e-6);
+        }
+    }
+}
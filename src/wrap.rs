@@ -55,6 +55,79 @@ pub fn c_emd_wrapper(
     }
 }
 
+#[allow(non_snake_case)]
+/// A direct wrapper around the generated `EMD_wrap_return_sparse` bindings.
+///
+/// Like [c_emd_wrapper], but instead of filling a dense `n1`x`n2` flow matrix, the C code
+/// writes only the non-zero arcs of the solved transportation problem as coordinate triplets.
+/// A network-simplex optimum has at most `n1 + n2 - 1` non-zero arcs, so `rows`/`cols`/`values`
+/// are pre-allocated with `n1 + n2` entries and truncated to the `nG` that were actually
+/// written.
+///
+/// Only use this directly if you absolutely need to. A simpler interface is given
+/// by [crate::emd_sparse].
+pub fn c_emd_wrapper_sparse(
+    a: &mut Array1<f64>,
+    b: &mut Array1<f64>,
+    M: &mut Array2<f64>,
+    max_iter: i32,
+) -> (
+    Array1<usize>,
+    Array1<usize>,
+    Array1<f64>,
+    f64,
+    Array1<f64>,
+    Array1<f64>,
+    i32,
+) {
+    let n_1 = M.len_of(Axis(0));
+    let n_2 = M.len_of(Axis(1));
+
+    let cap = n_1 + n_2;
+
+    let mut cost = 0f64;
+    let mut alpha = Array1::<f64>::zeros(n_1);
+    let mut beta = Array1::<f64>::zeros(n_2);
+
+    let mut rows_i32 = vec![0i32; cap];
+    let mut cols_i32 = vec![0i32; cap];
+    let mut values = Array1::<f64>::zeros(cap);
+    let mut n_g: i32 = 0;
+
+    if a.is_empty() {
+        *a = Array1::from_elem(n_1, 1f64 / n_1 as f64);
+    }
+
+    if b.is_empty() {
+        *b = Array1::from_elem(n_2, 1f64 / n_2 as f64);
+    }
+
+    let code = unsafe {
+        EMD_wrap_return_sparse(
+            n_1 as i32,
+            n_2 as i32,
+            a.as_mut_ptr(),
+            b.as_mut_ptr(),
+            M.as_mut_ptr(),
+            rows_i32.as_mut_ptr(),
+            cols_i32.as_mut_ptr(),
+            values.as_mut_ptr(),
+            &mut n_g,
+            alpha.as_mut_ptr(),
+            beta.as_mut_ptr(),
+            &mut cost,
+            max_iter,
+        )
+    };
+
+    let n_g = n_g as usize;
+    let rows = Array1::from_iter(rows_i32[..n_g].iter().map(|&i| i as usize));
+    let cols = Array1::from_iter(cols_i32[..n_g].iter().map(|&j| j as usize));
+    let values = values.slice(ndarray::s![..n_g]).to_owned();
+
+    (rows, cols, values, cost, alpha, beta, code)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;